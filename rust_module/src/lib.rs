@@ -4,6 +4,7 @@ use pyo3::prelude::*;
 use regex::Regex;
 use lazy_static::lazy_static;
 use crc32fast::Hasher as Crc32;
+use std::sync::RwLock;
 
 // --- 1. RUST REGEX ENGINE (PII GUARD) ---
 // Autómatas DFA pre-compilados. Velocidad O(n).
@@ -12,6 +13,7 @@ lazy_static! {
     static ref PHONE_RE: Regex = Regex::new(r"\+(9[976]\d|8[987530]\d|6[987]\d|5[90]\d|42\d|3[875]\d|2[98654321]\d|9[8543210]|8[6421]|6[6543210]|5[87654321]|4[987654310]|3[9643210]|2[70]|7|1)\d{1,14}").unwrap();
     static ref IP_RE: Regex = Regex::new(r"\b(?:\d{1,3}\.){3}\d{1,3}\b").unwrap();
     static ref CC_RE: Regex = Regex::new(r"\b(?:\d{4}[- ]?){3}\d{4}\b").unwrap();
+    static ref PII_TOKEN_RE: Regex = Regex::new(r"\{\{PII:([A-Z_]+):([A-Za-z0-9+/=]+)\}\}").unwrap();
 }
 
 /// Escanea texto ultra-rápido buscando PII.
@@ -39,37 +41,339 @@ pub fn scrub_pii_fast(text: &str) -> String {
     clean
 }
 
+/// Reemplaza cada PII detectado por un token cifrado reversible (AES-256-GCM) en vez de
+/// un marcador fijo como `<EMAIL>`. El *tipo* de PII va como AEAD associated data, así que
+/// un token `EMAIL` nunca puede reescribirse ni colarse en un slot `PHONE`.
+/// Token format: `{{PII:<TYPE>:base64(nonce || ciphertext || tag)}}`
+#[pyfunction]
+pub fn tokenize_pii_fast(text: &str, key: &[u8]) -> PyResult<String> {
+    use aes_gcm::aead::{Aead, OsRng as AeadOsRng, Payload};
+    use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+    use rand::RngCore;
+
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid key: {}", e)))?;
+
+    // Collect every candidate match over the *original* text up front. AES-GCM ciphertext is
+    // high-entropy base64 (alphabet includes `+` and digits), so if a later pass re-scanned
+    // the growing output, an earlier token's ciphertext could itself match e.g. PHONE_RE and
+    // get nested inside another `{{PII:...}}` token, corrupting it. Matching everything
+    // against the original text first, then merging overlapping matches into one span
+    // (below), makes that impossible.
+    let mut matches: Vec<(&str, regex::Match)> = Vec::new();
+    for (label, re) in [
+        ("EMAIL", &*EMAIL_RE),
+        ("PHONE", &*PHONE_RE),
+        ("IP_ADDRESS", &*IP_RE),
+        ("CREDIT_CARD", &*CC_RE),
+    ] {
+        matches.extend(re.find_iter(text).map(|m| (label, m)));
+    }
+    matches.sort_by_key(|(_, m)| m.start());
+
+    // Two matches from different regexes can partially overlap (share some bytes without
+    // either containing the other) -- e.g. a greedy PHONE_RE match immediately followed by
+    // more digits that independently satisfy CC_RE's shape one byte later. Tokenizing each
+    // match's span independently would either double-tokenize the shared bytes or, if the
+    // later one is simply skipped, drop or leak the bytes beyond the first match's end. So
+    // first collapse any matches whose spans overlap into a single span covering their
+    // union, tagged with the label of whichever match started it. Spans that merely touch
+    // (one ends where the next starts) stay separate tokens.
+    let mut spans: Vec<(&str, usize, usize)> = Vec::with_capacity(matches.len());
+    for (label, m) in matches {
+        match spans.last_mut() {
+            Some((_, _, end)) if m.start() < *end => {
+                *end = (*end).max(m.end());
+            }
+            _ => spans.push((label, m.start(), m.end())),
+        }
+    }
+
+    let mut out = String::with_capacity(text.len());
+    let mut cursor = 0usize;
+    for (label, start, end) in spans {
+        out.push_str(&text[cursor..start]);
+
+        let mut nonce_bytes = [0u8; 12];
+        AeadOsRng.fill_bytes(&mut nonce_bytes);
+        let nonce = Nonce::from_slice(&nonce_bytes);
+
+        let ciphertext = cipher
+            .encrypt(
+                nonce,
+                Payload {
+                    msg: text[start..end].as_bytes(),
+                    aad: label.as_bytes(),
+                },
+            )
+            .expect("AES-256-GCM encryption cannot fail for a well-formed key/nonce");
+
+        let mut combined = Vec::with_capacity(nonce_bytes.len() + ciphertext.len());
+        combined.extend_from_slice(&nonce_bytes);
+        combined.extend_from_slice(&ciphertext);
+
+        out.push_str(&format!("{{{{PII:{}:{}}}}}", label, BASE64.encode(combined)));
+        cursor = end;
+    }
+    out.push_str(&text[cursor..]);
+
+    Ok(out)
+}
+
+/// Inversa de [`tokenize_pii_fast`]: localiza cada marcador `{{PII:TYPE:...}}`, decodifica el
+/// payload y descifra. Un fallo de verificación del tag (dato corrupto, clave equivocada, o un
+/// token cuyo TYPE fue manipulado) se propaga como error en vez de devolver texto parcial.
+#[pyfunction]
+pub fn detokenize_pii_fast(text: &str, key: &[u8]) -> PyResult<String> {
+    use aes_gcm::aead::{Aead, Payload};
+    use aes_gcm::{Aes256Gcm, KeyInit, Nonce};
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+
+    let cipher = Aes256Gcm::new_from_slice(key)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid key: {}", e)))?;
+
+    let mut failure = None;
+    let result = PII_TOKEN_RE
+        .replace_all(text, |caps: &regex::Captures| {
+            let label = &caps[1];
+            let payload = match BASE64.decode(caps[2].as_bytes()) {
+                Ok(p) => p,
+                Err(_) => {
+                    failure = Some(format!("malformed base64 in {} token", label));
+                    return String::new();
+                }
+            };
+            if payload.len() < 12 {
+                failure = Some(format!("truncated {} token", label));
+                return String::new();
+            }
+            let (nonce_bytes, ciphertext) = payload.split_at(12);
+            let nonce = Nonce::from_slice(nonce_bytes);
+
+            match cipher.decrypt(
+                nonce,
+                Payload {
+                    msg: ciphertext,
+                    aad: label.as_bytes(),
+                },
+            ) {
+                Ok(plain) => String::from_utf8_lossy(&plain).into_owned(),
+                Err(_) => {
+                    failure = Some(format!("tag verification failed for {} token", label));
+                    String::new()
+                }
+            }
+        })
+        .to_string();
+
+    match failure {
+        Some(message) => Err(pyo3::exceptions::PyValueError::new_err(message)),
+        None => Ok(result),
+    }
+}
+
+// --- 1b. PLUGGABLE PII RULESET (VALIDATED SCANNING) ---
+// The raw EMAIL_RE/PHONE_RE/IP_RE/CC_RE above flag anything shape-matching a candidate,
+// even implausible ones (`999.999.999.999`, `1234-5678-9012-3456`). This adds a checksum-
+// validated scanning mode plus a runtime-extensible registry for custom detectors.
+
+struct PiiPattern {
+    label: String,
+    regex: Regex,
+    validator: Option<Py<PyAny>>,
+}
+
+lazy_static! {
+    static ref PII_REGISTRY: RwLock<Vec<PiiPattern>> = RwLock::new(Vec::new());
+}
+
+/// Luhn checksum, used to reject digit-shaped strings that aren't actually valid card numbers.
+fn luhn_check(candidate: &str) -> bool {
+    let digits: Vec<u32> = candidate.chars().filter_map(|c| c.to_digit(10)).collect();
+    if digits.is_empty() {
+        return false;
+    }
+    let mut sum = 0u32;
+    for (i, &digit) in digits.iter().rev().enumerate() {
+        let mut value = digit;
+        if i % 2 == 1 {
+            value *= 2;
+            if value > 9 {
+                value -= 9;
+            }
+        }
+        sum += value;
+    }
+    sum % 10 == 0
+}
+
+/// Rejects IPv4-shaped strings with an out-of-range octet (e.g. `999.999.999.999`).
+fn ipv4_octets_valid(candidate: &str) -> bool {
+    let parts: Vec<&str> = candidate.split('.').collect();
+    parts.len() == 4 && parts.iter().all(|p| p.parse::<u16>().map(|n| n <= 255).unwrap_or(false))
+}
+
+/// Rejects phone-shaped strings whose digit count falls outside the plausible E.164 range.
+fn phone_digit_count_plausible(candidate: &str) -> bool {
+    let digit_count = candidate.chars().filter(|c| c.is_ascii_digit()).count();
+    (8..=15).contains(&digit_count)
+}
+
+/// Como [`scan_pii_fast`], pero cada candidato debe pasar una validación específica de su
+/// tipo antes de ser reportado (Luhn para tarjetas, rango de octeto para IPs, conteo de
+/// dígitos plausible para teléfonos), y además recorre cualquier patrón registrado vía
+/// [`register_pii_pattern`]. Devuelve `(label, start, end)` en offsets de bytes.
+#[pyfunction]
+pub fn scan_pii_validated(text: &str) -> PyResult<Vec<(String, usize, usize)>> {
+    let mut hits = Vec::new();
+
+    for m in EMAIL_RE.find_iter(text) {
+        hits.push(("EMAIL".to_string(), m.start(), m.end()));
+    }
+    for m in PHONE_RE.find_iter(text) {
+        if phone_digit_count_plausible(m.as_str()) {
+            hits.push(("PHONE".to_string(), m.start(), m.end()));
+        }
+    }
+    for m in IP_RE.find_iter(text) {
+        if ipv4_octets_valid(m.as_str()) {
+            hits.push(("IP_ADDRESS".to_string(), m.start(), m.end()));
+        }
+    }
+    for m in CC_RE.find_iter(text) {
+        if luhn_check(m.as_str()) {
+            hits.push(("CREDIT_CARD".to_string(), m.start(), m.end()));
+        }
+    }
+
+    // Copy out what we need (the regex clones cheaply; Arc-backed) and drop the read guard
+    // before calling into any validator. A validator is arbitrary Python code and may itself
+    // call register_pii_pattern (or otherwise re-enter scanning), which needs a write() lock
+    // on this same, non-reentrant RwLock — holding the guard across that call would deadlock.
+    let entries: Vec<(String, Regex, Option<Py<PyAny>>)> = {
+        let registry = PII_REGISTRY.read().map_err(|_| {
+            pyo3::exceptions::PyRuntimeError::new_err("PII pattern registry poisoned")
+        })?;
+        registry
+            .iter()
+            .map(|p| {
+                let validator = p
+                    .validator
+                    .as_ref()
+                    .map(|v| Python::with_gil(|py| v.clone_ref(py)));
+                (p.label.clone(), p.regex.clone(), validator)
+            })
+            .collect()
+    };
+
+    for (label, regex, validator) in entries {
+        for m in regex.find_iter(text) {
+            let passes = match &validator {
+                Some(validator) => Python::with_gil(|py| {
+                    validator
+                        .call1(py, (m.as_str(),))
+                        .and_then(|r| r.extract::<bool>(py))
+                        .unwrap_or(false)
+                }),
+                None => true,
+            };
+            if passes {
+                hits.push((label.clone(), m.start(), m.end()));
+            }
+        }
+    }
+
+    hits.sort_by_key(|(_, start, _)| *start);
+    Ok(hits)
+}
+
+/// Registra un detector de PII personalizado (p.ej. IBAN, DNI) en el registro compartido,
+/// compilando su regex una sola vez. Un `validator` opcional (callable de Python que recibe
+/// el texto candidato y devuelve `bool`) filtra falsos positivos igual que Luhn/IP/phone lo
+/// hacen para los tipos integrados.
+#[pyfunction]
+#[pyo3(signature = (label, regex, validator=None))]
+pub fn register_pii_pattern(label: &str, regex: &str, validator: Option<Py<PyAny>>) -> PyResult<()> {
+    let compiled = Regex::new(regex)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid regex: {}", e)))?;
+
+    let mut registry = PII_REGISTRY
+        .write()
+        .map_err(|_| pyo3::exceptions::PyRuntimeError::new_err("PII pattern registry poisoned"))?;
+    registry.push(PiiPattern {
+        label: label.to_string(),
+        regex: compiled,
+        validator,
+    });
+    Ok(())
+}
+
+/// Computes the Shannon entropy (in bits) of `token`'s character distribution.
+fn shannon_entropy(token: &str) -> f64 {
+    let mut entropy = 0.0;
+    let len = token.len() as f64;
+
+    let mut counts = std::collections::HashMap::new();
+    for c in token.chars() {
+        *counts.entry(c).or_insert(0) += 1;
+    }
+
+    for &count in counts.values() {
+        let p = count as f64 / len;
+        entropy -= p * p.log2();
+    }
+
+    entropy
+}
+
 /// Detects high entropy strings (potential secrets) in Rust.
 /// Shannon Entropy > 4.5 is suspicious.
 #[pyfunction]
 pub fn scan_entropy_fast(text: &str) -> Vec<String> {
     let mut secrets = Vec::new();
-    
+
     for token in text.split_whitespace() {
         if token.len() < 8 { continue; }
-        
-        let mut entropy = 0.0;
-        let len = token.len() as f64;
-        
-        // Count char frequencies
-        let mut counts = std::collections::HashMap::new();
-        for c in token.chars() {
-            *counts.entry(c).or_insert(0) += 1;
-        }
-        
-        for &count in counts.values() {
-            let p = count as f64 / len;
-            entropy -= p * p.log2();
-        }
-        
+
         // Threshold 4.5
-        if entropy > 4.5 {
+        if shannon_entropy(token) > 4.5 {
              secrets.push(token.to_string());
         }
     }
     secrets
 }
 
+/// Como [`scan_entropy_fast`], pero en vez de clonar el texto del secreto en un `String`
+/// devuelve solo su offset en bytes (`start`, `end`) y el score de entropía, así el texto
+/// plano del secreto nunca se clona en un objeto Python; el caller puede redactar in place
+/// sobre el buffer original.
+#[pyfunction]
+pub fn scan_entropy_offsets(text: &str) -> Vec<(usize, usize, f64)> {
+    let mut hits = Vec::new();
+
+    for (start, token) in token_offsets(text) {
+        if token.len() < 8 {
+            continue;
+        }
+
+        let entropy = shannon_entropy(token);
+        if entropy > 4.5 {
+            hits.push((start, start + token.len(), entropy));
+        }
+    }
+    hits
+}
+
+/// Yields each whitespace-delimited token of `text` along with its starting byte offset,
+/// matching the same tokenization `scan_entropy_fast` uses via `split_whitespace`.
+fn token_offsets(text: &str) -> impl Iterator<Item = (usize, &str)> {
+    text.split_whitespace().map(move |token| {
+        let start = (token.as_ptr() as usize) - (text.as_ptr() as usize);
+        (start, token)
+    })
+}
+
 // --- 2. ZERO-COPY IMAGE SIGNING (C2PA - Manual Binary Injection) ---
 #[pyfunction]
 pub fn sign_c2pa_image_fast(
@@ -81,27 +385,44 @@ pub fn sign_c2pa_image_fast(
 // ... existing impl remains same ...
     // A. Firma Criptográfica
     // Use Fully Qualified syntax to avoid trait confusion
-    use rsa::pkcs8::DecodePrivateKey;
-    use rsa::{RsaPrivateKey, Pkcs1v15Sign};
+    use rsa::pkcs8::{DecodePrivateKey, EncodePublicKey};
+    use rsa::{RsaPrivateKey, RsaPublicKey, Pkcs1v15Sign};
     use sha2::{Sha256, Digest};
     use base64::{Engine as _, engine::general_purpose::STANDARD as BASE64};
+    use zeroize::Zeroizing;
 
-    let private_key = RsaPrivateKey::from_pkcs8_pem(private_key_pem)
+    // Work from our own zeroizing copy of the PEM rather than parsing `private_key_pem`
+    // directly: we don't own the caller's buffer, but everything we derive from it here does
+    // get wiped.
+    let private_key_pem_owned = Zeroizing::new(private_key_pem.to_string());
+    // `RsaPrivateKey` only implements `ZeroizeOnDrop` (a marker backed by its own `Drop`
+    // impl), not `Zeroize`, so it can't be wrapped in `Zeroizing` -- its `Drop` impl already
+    // wipes the key material unconditionally.
+    let private_key = RsaPrivateKey::from_pkcs8_pem(&private_key_pem_owned)
         .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid Key: {}", e)))?;
+    let public_key = RsaPublicKey::from(&private_key);
+    let public_key_der = public_key.to_public_key_der()
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid key: {}", e)))?;
 
     let mut hasher = Sha256::new();
     hasher.update(manifest_json.as_bytes());
-    let hashed = hasher.finalize();
+    let hashed = Zeroizing::new(hasher.finalize().to_vec());
 
     // Explicitly cast traits if needed, but standard usage should work.
-    let signature = private_key.sign(Pkcs1v15Sign::new::<Sha256>(), &hashed)
-        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Signing failed: {}", e)))?;
-    
-    let signature_b64 = BASE64.encode(signature);
+    let signature = Zeroizing::new(
+        private_key.sign(Pkcs1v15Sign::new::<Sha256>(), &hashed)
+            .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Signing failed: {}", e)))?,
+    );
+
+    let signature_b64 = BASE64.encode(&*signature);
 
     let full_payload = serde_json::json!({
         "manifest": serde_json::from_str::<serde_json::Value>(manifest_json).unwrap_or_default(),
+        // Kept verbatim (not the re-serialized "manifest" value above) so verification can
+        // re-hash the exact bytes that were signed.
+        "manifest_raw": manifest_json,
         "signature": signature_b64,
+        "public_key": BASE64.encode(public_key_der.as_bytes()),
         "algo": "rsa-sha256-rust-zero-copy"
     });
     let payload_str = full_payload.to_string();
@@ -166,6 +487,169 @@ pub fn sign_c2pa_image_fast(
     Ok(pyo3::types::PyBytes::new_bound(py, &output_vec).unbind().into())
 }
 
+// --- 3. SHAMIR SECRET SHARING (C2PA KEY CUSTODY) ---
+// GF(2^8) arithmetic using the AES reduction polynomial 0x11b, with
+// precomputed log/antilog tables so division (needed by Lagrange
+// interpolation) is a single table lookup instead of a loop.
+
+lazy_static! {
+    static ref GF256_EXP: [u8; 512] = {
+        let mut exp = [0u8; 512];
+        // Generator 3 (the field element 0x03), not 2: 2's multiplicative order under the
+        // AES reduction polynomial 0x11b is only 51, which would silently collapse this
+        // table into a 51-element subgroup instead of enumerating all 255 nonzero elements.
+        // 3 is a primitive element (order 255) for this modulus, so x *= 3 each step walks
+        // the whole multiplicative group.
+        let mut x: u8 = 1;
+        for i in 0..255 {
+            exp[i] = x;
+            let doubled = {
+                let d = (x as u16) << 1;
+                if d & 0x100 != 0 {
+                    (d ^ 0x11b) as u8
+                } else {
+                    d as u8
+                }
+            };
+            x = doubled ^ x; // x * 3 = (x * 2) XOR x
+        }
+        for i in 255..512 {
+            exp[i] = exp[i - 255];
+        }
+        exp
+    };
+    static ref GF256_LOG: [u8; 256] = {
+        let mut log = [0u8; 256];
+        for i in 0..255 {
+            log[GF256_EXP[i] as usize] = i as u8;
+        }
+        log
+    };
+}
+
+fn gf256_mul(a: u8, b: u8) -> u8 {
+    if a == 0 || b == 0 {
+        return 0;
+    }
+    let log_sum = GF256_LOG[a as usize] as usize + GF256_LOG[b as usize] as usize;
+    GF256_EXP[log_sum]
+}
+
+fn gf256_div(a: u8, b: u8) -> u8 {
+    assert!(b != 0, "division by zero in GF(256)");
+    if a == 0 {
+        return 0;
+    }
+    let log_diff = GF256_LOG[a as usize] as i32 - GF256_LOG[b as usize] as i32 + 255;
+    GF256_EXP[(log_diff as usize) % 255]
+}
+
+/// Evalúa el polinomio `coeffs` (coeffs[0] = término constante) en `x`, en GF(256).
+fn gf256_eval(coeffs: &[u8], x: u8) -> u8 {
+    // Horner's method, high degree to low.
+    let mut result = 0u8;
+    for &coeff in coeffs.iter().rev() {
+        result = gf256_mul(result, x) ^ coeff;
+    }
+    result
+}
+
+/// Divide `secret` en `shares` fragmentos tales que cualquier `threshold` de ellos
+/// reconstruyen el secreto, pero `threshold - 1` no revelan nada (Shamir's Secret Sharing
+/// sobre GF(2^8), byte a byte). Cada share es `[x] || [evaluated byte per secret byte]`.
+#[pyfunction]
+pub fn split_key_shares(secret: &[u8], threshold: u8, shares: u8) -> PyResult<Vec<Vec<u8>>> {
+    use rand::RngCore;
+
+    if threshold == 0 {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "threshold must be at least 1",
+        ));
+    }
+    if threshold > shares {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "threshold cannot exceed the number of shares",
+        ));
+    }
+
+    let mut rng = rand::rngs::OsRng;
+    let mut outputs: Vec<Vec<u8>> = (1..=shares).map(|x| vec![x]).collect();
+
+    for &secret_byte in secret {
+        // coeffs[0] is the secret byte itself; the rest are random (the polynomial's
+        // higher-order terms), making any (threshold - 1) shares information-theoretically
+        // independent of the secret.
+        let mut coeffs = vec![0u8; threshold as usize];
+        coeffs[0] = secret_byte;
+        if threshold > 1 {
+            rng.fill_bytes(&mut coeffs[1..]);
+        }
+
+        for (i, output) in outputs.iter_mut().enumerate() {
+            let x = (i + 1) as u8;
+            output.push(gf256_eval(&coeffs, x));
+        }
+    }
+
+    Ok(outputs)
+}
+
+/// Reconstruye el secreto a partir de `threshold` (o más) shares vía interpolación de
+/// Lagrange evaluada en x=0, en GF(2^8). Rechaza coordenadas x duplicadas.
+#[pyfunction]
+pub fn recover_key(shares: Vec<Vec<u8>>) -> PyResult<Vec<u8>> {
+    if shares.is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "at least one share is required",
+        ));
+    }
+
+    if shares[0].is_empty() {
+        return Err(pyo3::exceptions::PyValueError::new_err(
+            "each share must contain at least an x-coordinate byte",
+        ));
+    }
+    let secret_len = shares[0].len() - 1;
+    let mut xs = Vec::with_capacity(shares.len());
+    for share in &shares {
+        if share.len() != secret_len + 1 {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "all shares must have the same length",
+            ));
+        }
+        let x = share[0];
+        if xs.contains(&x) {
+            return Err(pyo3::exceptions::PyValueError::new_err(
+                "duplicate x-coordinate among shares",
+            ));
+        }
+        xs.push(x);
+    }
+
+    let mut secret = Vec::with_capacity(secret_len);
+    for byte_idx in 0..secret_len {
+        // Lagrange interpolation at x=0: secret_byte = sum_i y_i * prod_{j != i} (-x_j) / (x_i - x_j)
+        // In GF(2^8), subtraction is XOR and "-x_j" is just x_j.
+        let mut acc = 0u8;
+        for (i, share) in shares.iter().enumerate() {
+            let y_i = share[byte_idx + 1];
+            let mut numerator = 1u8;
+            let mut denominator = 1u8;
+            for (j, &x_j) in xs.iter().enumerate() {
+                if i == j {
+                    continue;
+                }
+                numerator = gf256_mul(numerator, x_j);
+                denominator = gf256_mul(denominator, xs[i] ^ x_j);
+            }
+            acc ^= gf256_mul(y_i, gf256_div(numerator, denominator));
+        }
+        secret.push(acc);
+    }
+
+    Ok(secret)
+}
+
 /// -------------------------------------------------------------------------
 /// C2PA / Content Authenticity Signing
 /// -------------------------------------------------------------------------
@@ -174,40 +658,343 @@ pub fn sign_c2pa_image_fast(
 /// Retorna un JSON string con { "hash": "...", "signature": "...", "public_key": "..." }
 #[pyfunction]
 fn sign_c2pa_manifest(content: &str, author_id: &str) -> PyResult<String> {
+    use ed25519_dalek::{Signer, SigningKey};
+    use rand::rngs::OsRng;
+    use sha2::{Digest, Sha256};
+    use zeroize::Zeroizing;
+
     // 1. Hash del contenido (SHA-256)
     let mut hasher = Sha256::new();
     hasher.update(content.as_bytes());
-    let content_hash = hasher.finalize();
-    let content_hash_hex = hex::encode(content_hash);
+    let content_hash = Zeroizing::new(hasher.finalize().to_vec());
+    let content_hash_hex = hex::encode(&*content_hash);
 
-    // 2. Generar par de claves efímeras para demo (En prod usarían claves persistentes)
+    // 2. Generar par de claves efímeras para demo (En prod usarían claves persistentes).
+    // `SigningKey` only implements `ZeroizeOnDrop` (a marker backed by its own `Drop` impl),
+    // not `Zeroize`, so it can't be wrapped in `Zeroizing` -- its `Drop` impl already wipes
+    // the seed unconditionally.
     let mut csprng = OsRng;
     let signing_key = SigningKey::generate(&mut csprng);
     let verifying_key = signing_key.verifying_key();
-    
+
     // 3. Crear el payload a firmar (Manifest)
     let manifest_payload = format!("{}:{}:{}", author_id, content_hash_hex, "AgentShield-C2PA-v1");
-    
+
     // 4. Firmar
     let signature = signing_key.sign(manifest_payload.as_bytes());
-    
+
     // 5. Encode a Base64
     let signature_b64 = general_purpose::STANDARD.encode(signature.to_bytes());
     let public_key_b64 = general_purpose::STANDARD.encode(verifying_key.to_bytes());
     
-    // 6. Construir JSON de respuesta
-    let json_response = format!(
-        r#"{{
-            "content_hash": "{}",
-            "signature": "{}",
-            "public_key": "{}",
-            "algo": "ed25519",
-            "manifest_version": "c2pa.v1.demo"
-        }}"#,
-        content_hash_hex, signature_b64, public_key_b64
-    );
+    // 6. Construir JSON de respuesta (author_id se incluye para que
+    // verify_c2pa_manifest pueda reconstruir el payload firmado)
+    let json_response = serde_json::json!({
+        "author_id": author_id,
+        "content_hash": content_hash_hex,
+        "signature": signature_b64,
+        "public_key": public_key_b64,
+        "algo": "ed25519",
+        "manifest_version": "c2pa.v1.demo"
+    });
+
+    Ok(json_response.to_string())
+}
+
+/// Verifica una firma producida por [`sign_c2pa_manifest`]: reconstruye el payload
+/// `{author_id}:{content_hash}:AgentShield-C2PA-v1`, decodifica la firma y la clave pública
+/// ed25519 embebidas en el JSON, y comprueba que firman ese payload exacto.
+#[pyfunction]
+fn verify_c2pa_manifest(json: &str) -> PyResult<bool> {
+    use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+
+    let parsed: serde_json::Value = serde_json::from_str(json)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid manifest JSON: {}", e)))?;
+
+    let author_id = parsed
+        .get("author_id")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("manifest missing author_id"))?;
+    let content_hash = parsed
+        .get("content_hash")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("manifest missing content_hash"))?;
+    let signature_b64 = parsed
+        .get("signature")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("manifest missing signature"))?;
+    let public_key_b64 = parsed
+        .get("public_key")
+        .and_then(|v| v.as_str())
+        .ok_or_else(|| pyo3::exceptions::PyValueError::new_err("manifest missing public_key"))?;
+
+    let manifest_payload = format!("{}:{}:{}", author_id, content_hash, "AgentShield-C2PA-v1");
+
+    let signature_bytes = general_purpose::STANDARD
+        .decode(signature_b64)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid signature encoding: {}", e)))?;
+    let public_key_bytes = general_purpose::STANDARD
+        .decode(public_key_b64)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid public key encoding: {}", e)))?;
+
+    let signature = Signature::from_slice(&signature_bytes)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Malformed signature: {}", e)))?;
+    let public_key_arr: [u8; 32] = public_key_bytes
+        .try_into()
+        .map_err(|_| pyo3::exceptions::PyValueError::new_err("public_key must be 32 bytes"))?;
+    let verifying_key = VerifyingKey::from_bytes(&public_key_arr)
+        .map_err(|e| pyo3::exceptions::PyValueError::new_err(format!("Invalid public key: {}", e)))?;
+
+    Ok(verifying_key.verify(manifest_payload.as_bytes(), &signature).is_ok())
+}
+
+// --- PNG / JPEG METADATA SCANNING (used by the verify image path) ---
+
+/// Devuelve el contenido de cada chunk PNG `tEXt` cuyo keyword coincide, tolerando
+/// chunks truncados (se detiene el escaneo en vez de entrar en pánico).
+fn extract_png_text_chunks<'a>(bytes: &'a [u8], keyword: &str) -> Vec<&'a [u8]> {
+    let mut out = Vec::new();
+    if !bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        return out;
+    }
+
+    let mut pos = 8usize;
+    while pos + 8 <= bytes.len() {
+        let length = u32::from_be_bytes([bytes[pos], bytes[pos + 1], bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let chunk_type = &bytes[pos + 4..pos + 8];
+        let data_start = pos + 8;
+        let data_end = match data_start.checked_add(length) {
+            Some(end) if end + 4 <= bytes.len() => end,
+            _ => break, // truncated chunk
+        };
+
+        if chunk_type == b"tEXt" {
+            let chunk_data = &bytes[data_start..data_end];
+            if let Some(null_pos) = chunk_data.iter().position(|&b| b == 0) {
+                if &chunk_data[..null_pos] == keyword.as_bytes() {
+                    out.push(&chunk_data[null_pos + 1..]);
+                }
+            }
+        }
+
+        if chunk_type == b"IEND" {
+            break;
+        }
+        pos = data_end + 4; // skip CRC
+    }
+    out
+}
+
+/// Devuelve el contenido de cada segmento JPEG `COM` (marcador 0xFFFE), tolerando
+/// segmentos truncados (se detiene el escaneo en vez de entrar en pánico).
+fn extract_jpeg_com_segments(bytes: &[u8]) -> Vec<&[u8]> {
+    let mut out = Vec::new();
+    if !bytes.starts_with(b"\xff\xd8") {
+        return out;
+    }
+
+    let mut pos = 2usize;
+    while pos + 2 <= bytes.len() && bytes[pos] == 0xFF {
+        let marker = bytes[pos + 1];
+        if marker == 0xD9 {
+            break; // EOI
+        }
+
+        // TEM (0x01) and the restart markers RST0-RST7 (0xD0-0xD7) are standalone: unlike
+        // every other marker segment they carry no 2-byte length field, just the marker
+        // itself. Treating them as length-prefixed would read two unrelated payload bytes
+        // as a bogus length and desync the scan for the rest of the file.
+        if marker == 0x01 || marker == 0xD8 || (0xD0..=0xD7).contains(&marker) {
+            pos += 2;
+            continue;
+        }
+
+        if pos + 4 > bytes.len() {
+            break; // truncated segment
+        }
+        let length = u16::from_be_bytes([bytes[pos + 2], bytes[pos + 3]]) as usize;
+        let segment_end = match pos.checked_add(2 + length) {
+            Some(end) if length >= 2 && end <= bytes.len() => end,
+            _ => break, // truncated segment
+        };
+
+        if marker == 0xFE {
+            out.push(&bytes[pos + 4..segment_end]);
+        }
+        pos = segment_end;
+    }
+    out
+}
+
+/// Verifica una firma producida por [`sign_c2pa_image_fast`]: localiza el chunk PNG `tEXt`
+/// o segmento JPEG `COM` inyectado (tolerando múltiples segmentos de metadata), re-calcula el
+/// hash SHA-256 del manifiesto embebido, y valida la firma RSA-PKCS1v15. Nunca lanza una
+/// excepción por un fallo de verificación: `valid=false` lo indica para que el caller pueda
+/// ramificar limpiamente.
+#[pyfunction]
+fn verify_c2pa_image_fast(py: Python<'_>, image_bytes: &[u8]) -> PyResult<PyObject> {
+    use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+    use pyo3::types::PyDict;
+    use rsa::pkcs8::DecodePublicKey;
+    use rsa::{Pkcs1v15Sign, RsaPublicKey};
+    use sha2::{Digest, Sha256};
+
+    let mut segments = extract_png_text_chunks(image_bytes, "AgentShield-C2PA");
+    if segments.is_empty() {
+        segments = extract_jpeg_com_segments(image_bytes);
+    }
+
+    let result = PyDict::new_bound(py);
+    let mut fallback: Option<(String, String)> = None;
+
+    for segment in segments {
+        let text = match std::str::from_utf8(segment) {
+            Ok(t) => t,
+            Err(_) => continue,
+        };
+        let parsed: serde_json::Value = match serde_json::from_str(text) {
+            Ok(v) => v,
+            Err(_) => continue,
+        };
+        let manifest_raw = match parsed.get("manifest_raw").and_then(|v| v.as_str()) {
+            Some(s) => s,
+            None => continue,
+        };
+        let signature_b64 = match parsed.get("signature").and_then(|v| v.as_str()) {
+            Some(s) => s,
+            None => continue,
+        };
+        let public_key_b64 = match parsed.get("public_key").and_then(|v| v.as_str()) {
+            Some(s) => s,
+            None => continue,
+        };
+
+        let algo = parsed.get("algo").and_then(|v| v.as_str()).unwrap_or("unknown");
+        let author = parsed
+            .get("manifest")
+            .and_then(|m| m.get("author").or_else(|| m.get("author_id")))
+            .and_then(|v| v.as_str())
+            .unwrap_or("");
+
+        let valid = (|| -> Option<bool> {
+            let signature_bytes = BASE64.decode(signature_b64).ok()?;
+            let public_key_der = BASE64.decode(public_key_b64).ok()?;
+            let public_key = RsaPublicKey::from_public_key_der(&public_key_der).ok()?;
+
+            let mut hasher = Sha256::new();
+            hasher.update(manifest_raw.as_bytes());
+            let hashed = hasher.finalize();
+
+            Some(
+                public_key
+                    .verify(Pkcs1v15Sign::new::<Sha256>(), &hashed, &signature_bytes)
+                    .is_ok(),
+            )
+        })()
+        .unwrap_or(false);
 
-    Ok(json_response)
+        if valid {
+            result.set_item("valid", true)?;
+            result.set_item("author", author)?;
+            result.set_item("algo", algo)?;
+            return Ok(result.into());
+        }
+
+        // A single bad or tampered segment shouldn't sink verification for the whole image —
+        // keep scanning the rest. Remember the first parseable-but-invalid segment's fields
+        // so the result is still informative if nothing ever validates.
+        fallback.get_or_insert_with(|| (author.to_string(), algo.to_string()));
+    }
+
+    let (author, algo) = fallback.unwrap_or_default();
+    result.set_item("valid", false)?;
+    result.set_item("author", author)?;
+    result.set_item("algo", algo)?;
+    Ok(result.into())
+}
+
+// --- 4. INVISIBLE TEXT WATERMARKING ---
+// Hides a short signed payload inside plain text using zero-width codepoints: each bit
+// becomes a ZERO WIDTH SPACE (U+200B = 0) or ZERO WIDTH NON-JOINER (U+200C = 1), framed by
+// ZERO WIDTH JOINER (U+200D) sentinels so extraction can locate and terminate the run
+// without touching any visible character.
+
+const ZW_SENTINEL: char = '\u{200D}';
+const ZW_ZERO: char = '\u{200B}';
+const ZW_ONE: char = '\u{200C}';
+
+fn bytes_to_zw_bits(payload: &[u8]) -> String {
+    let mut out = String::with_capacity(payload.len() * 8);
+    for byte in payload {
+        for bit in (0..8).rev() {
+            out.push(if (byte >> bit) & 1 == 1 { ZW_ONE } else { ZW_ZERO });
+        }
+    }
+    out
+}
+
+/// Inserta `payload` (p.ej. author id + firma truncada) como caracteres invisibles tras la
+/// primera palabra del texto. El texto visible, una vez despojado de los codepoints de ancho
+/// cero, queda byte a byte idéntico al original.
+#[pyfunction]
+pub fn embed_text_watermark(text: &str, payload: &[u8]) -> String {
+    let bitstream = bytes_to_zw_bits(payload);
+    let mut watermark = String::with_capacity(bitstream.len() + 2);
+    watermark.push(ZW_SENTINEL);
+    watermark.push_str(&bitstream);
+    watermark.push(ZW_SENTINEL);
+
+    match text.find(char::is_whitespace) {
+        Some(idx) => {
+            let (head, tail) = text.split_at(idx);
+            format!("{}{}{}", head, watermark, tail)
+        }
+        None => format!("{}{}", text, watermark),
+    }
+}
+
+/// Extrae el payload embebido por [`embed_text_watermark`], si existe. Es tolerante a
+/// cualquier espacio en blanco u otro texto alrededor del watermark: solo consume los
+/// codepoints de ancho cero que caen entre los dos centinelas.
+#[pyfunction]
+pub fn extract_text_watermark(text: &str) -> Option<Vec<u8>> {
+    let mut chars = text.chars();
+
+    // Find the opening sentinel.
+    loop {
+        match chars.next() {
+            Some(c) if c == ZW_SENTINEL => break,
+            Some(_) => continue,
+            None => return None,
+        }
+    }
+
+    let mut bits = Vec::new();
+    for c in chars {
+        match c {
+            ZW_SENTINEL => break,
+            ZW_ZERO => bits.push(0u8),
+            ZW_ONE => bits.push(1u8),
+            _ => continue,
+        }
+    }
+
+    if bits.is_empty() {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(bits.len() / 8);
+    for chunk in bits.chunks(8) {
+        if chunk.len() < 8 {
+            break;
+        }
+        let mut byte = 0u8;
+        for &bit in chunk {
+            byte = (byte << 1) | bit;
+        }
+        bytes.push(byte);
+    }
+    Some(bytes)
 }
 
 /// El módulo Python
@@ -216,8 +1003,19 @@ fn agentshield_rust(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(sign_c2pa_image_fast, m)?)?;
     m.add_function(wrap_pyfunction!(scan_pii_fast, m)?)?;
     m.add_function(wrap_pyfunction!(scrub_pii_fast, m)?)?;
+    m.add_function(wrap_pyfunction!(tokenize_pii_fast, m)?)?;
+    m.add_function(wrap_pyfunction!(detokenize_pii_fast, m)?)?;
+    m.add_function(wrap_pyfunction!(scan_pii_validated, m)?)?;
+    m.add_function(wrap_pyfunction!(register_pii_pattern, m)?)?;
     m.add_function(wrap_pyfunction!(scan_entropy_fast, m)?)?;
+    m.add_function(wrap_pyfunction!(scan_entropy_offsets, m)?)?;
     m.add_function(wrap_pyfunction!(sign_c2pa_manifest, m)?)?; // <--- NEW!
+    m.add_function(wrap_pyfunction!(verify_c2pa_manifest, m)?)?;
+    m.add_function(wrap_pyfunction!(verify_c2pa_image_fast, m)?)?;
+    m.add_function(wrap_pyfunction!(split_key_shares, m)?)?;
+    m.add_function(wrap_pyfunction!(recover_key, m)?)?;
+    m.add_function(wrap_pyfunction!(embed_text_watermark, m)?)?;
+    m.add_function(wrap_pyfunction!(extract_text_watermark, m)?)?;
     Ok(())
 }
 
@@ -281,6 +1079,68 @@ mod tests {
         assert_eq!(result, "Hello world");
     }
 
+    // --- PII TOKENIZATION TESTS ---
+
+    #[test]
+    fn test_tokenize_roundtrip() {
+        let key = [7u8; 32];
+        let tokenized = tokenize_pii_fast("Email: test@test.com", &key).unwrap();
+        assert!(!tokenized.contains("test@test.com"));
+        assert!(tokenized.contains("{{PII:EMAIL:"));
+
+        let restored = detokenize_pii_fast(&tokenized, &key).unwrap();
+        assert_eq!(restored, "Email: test@test.com");
+    }
+
+    #[test]
+    fn test_detokenize_wrong_key_fails() {
+        let key = [7u8; 32];
+        let other_key = [9u8; 32];
+        let tokenized = tokenize_pii_fast("Card 4111 1111 1111 1111", &key).unwrap();
+        assert!(detokenize_pii_fast(&tokenized, &other_key).is_err());
+    }
+
+    #[test]
+    fn test_tokenize_preserves_normal_text() {
+        let key = [1u8; 32];
+        let result = tokenize_pii_fast("Hello world", &key).unwrap();
+        assert_eq!(result, "Hello world");
+    }
+
+    #[test]
+    fn test_tokenize_never_nests_tokens_across_many_trials() {
+        // Ciphertext is high-entropy and its base64 alphabet can incidentally look like a
+        // phone number or IP. A correct implementation matches against the original text
+        // once, so no token can ever end up nested inside another.
+        let key = [3u8; 32];
+        for _ in 0..2000 {
+            let tokenized =
+                tokenize_pii_fast("Email: alice@example.com and nothing else", &key).unwrap();
+            assert_eq!(tokenized.matches("{{PII:").count(), 1);
+            let restored = detokenize_pii_fast(&tokenized, &key).unwrap();
+            assert_eq!(restored, "Email: alice@example.com and nothing else");
+        }
+    }
+
+    #[test]
+    fn test_tokenize_covers_partially_overlapping_matches() {
+        // PHONE_RE greedily matches the first 16 characters, "+123456789012345", as a
+        // US-style number (span 0..16), but CC_RE's `\b(\d{4}[- ]?){3}\d{4}\b` independently matches the
+        // 16 contiguous digits one byte in (span 1..17) since the whole run is one `\w`
+        // sequence with a single leading word boundary. Neither span contains the other, so
+        // they must collapse into a single merged token covering bytes 0..17 -- anything
+        // less either leaks byte 16 as plaintext or drops it from the round trip entirely.
+        let key = [5u8; 32];
+        let text = "+1234567890123456 end";
+        let tokenized = tokenize_pii_fast(text, &key).unwrap();
+        assert_eq!(tokenized.matches("{{PII:").count(), 1);
+        assert!(!tokenized.contains("1234567890123456"));
+        assert!(tokenized.ends_with(" end"));
+
+        let restored = detokenize_pii_fast(&tokenized, &key).unwrap();
+        assert_eq!(restored, text);
+    }
+
     // --- ENTROPY DETECTION TESTS ---
 
     #[test]
@@ -304,5 +1164,239 @@ mod tests {
         let secrets = scan_entropy_fast("abc 123 def");
         assert!(secrets.is_empty());
     }
+
+    #[test]
+    fn test_entropy_offsets_match_text_tokens() {
+        let text = "Token: aB3xK9mZ2pQ7wE5vR8nL4jH6gF1cD0sY";
+        let offsets = scan_entropy_offsets(text);
+        let tokens = scan_entropy_fast(text);
+        for ((start, end, score), token) in offsets.iter().zip(&tokens) {
+            assert!(*score > 4.5);
+            assert_eq!(&text[*start..*end], token.as_str());
+        }
+        assert_eq!(offsets.len(), tokens.len());
+    }
+
+    #[test]
+    fn test_entropy_offsets_empty_for_low_entropy() {
+        assert!(scan_entropy_offsets("The quick brown fox").is_empty());
+    }
+
+    // --- VALIDATED PII SCANNING TESTS ---
+
+    #[test]
+    fn test_validated_rejects_invalid_credit_card() {
+        // Shape-matches CC_RE but fails the Luhn checksum.
+        let hits = scan_pii_validated("Card: 1234-5678-9012-3456").unwrap();
+        assert!(hits.iter().all(|(label, _, _)| label != "CREDIT_CARD"));
+    }
+
+    #[test]
+    fn test_validated_accepts_valid_credit_card() {
+        let hits = scan_pii_validated("Card: 4111 1111 1111 1111").unwrap();
+        assert!(hits.iter().any(|(label, _, _)| label == "CREDIT_CARD"));
+    }
+
+    #[test]
+    fn test_validated_rejects_invalid_ip() {
+        let hits = scan_pii_validated("Server IP: 999.999.999.999").unwrap();
+        assert!(hits.iter().all(|(label, _, _)| label != "IP_ADDRESS"));
+    }
+
+    #[test]
+    fn test_validated_accepts_valid_ip() {
+        let hits = scan_pii_validated("Server IP: 192.168.1.100").unwrap();
+        assert!(hits.iter().any(|(label, _, _)| label == "IP_ADDRESS"));
+    }
+
+    #[test]
+    fn test_register_and_match_custom_pattern() {
+        register_pii_pattern(
+            "TEST_IBAN_MARKER",
+            r"\bXX00TESTIBAN\d{4}\b",
+            None,
+        )
+        .unwrap();
+
+        let hits = scan_pii_validated("Account: XX00TESTIBAN1234 on file").unwrap();
+        assert!(hits
+            .iter()
+            .any(|(label, _, _)| label == "TEST_IBAN_MARKER"));
+    }
+
+    // --- SHAMIR SECRET SHARING TESTS ---
+
+    #[test]
+    fn test_split_and_recover_roundtrip() {
+        let secret = b"super-secret-signing-key".to_vec();
+        let shares = split_key_shares(&secret, 3, 5).unwrap();
+        assert_eq!(shares.len(), 5);
+
+        let recovered = recover_key(shares[1..4].to_vec()).unwrap();
+        assert_eq!(recovered, secret);
+    }
+
+    #[test]
+    fn test_recover_any_threshold_subset() {
+        let secret = b"rotating-key".to_vec();
+        let shares = split_key_shares(&secret, 2, 4).unwrap();
+
+        let recovered = recover_key(vec![shares[0].clone(), shares[3].clone()]).unwrap();
+        assert_eq!(recovered, secret);
+    }
+
+    #[test]
+    fn test_split_rejects_threshold_greater_than_shares() {
+        assert!(split_key_shares(b"x", 5, 3).is_err());
+    }
+
+    #[test]
+    fn test_split_rejects_zero_threshold() {
+        assert!(split_key_shares(b"x", 0, 3).is_err());
+    }
+
+    #[test]
+    fn test_recover_rejects_duplicate_x_coordinates() {
+        let secret = b"abc".to_vec();
+        let shares = split_key_shares(&secret, 2, 3).unwrap();
+        let duplicated = vec![shares[0].clone(), shares[0].clone()];
+        assert!(recover_key(duplicated).is_err());
+    }
+
+    #[test]
+    fn test_recover_rejects_empty_share_without_panicking() {
+        assert!(recover_key(vec![vec![]]).is_err());
+    }
+
+    #[test]
+    fn test_split_and_recover_roundtrip_for_every_byte_value() {
+        for b in 0..=255u8 {
+            let secret = vec![b];
+            let shares = split_key_shares(&secret, 3, 5).unwrap();
+            let recovered = recover_key(shares[1..4].to_vec()).unwrap();
+            assert_eq!(recovered, secret, "roundtrip failed for byte {}", b);
+        }
+    }
+
+    // --- TEXT WATERMARKING TESTS ---
+
+    #[test]
+    fn test_watermark_roundtrip() {
+        let text = "Hello world, this is agent output.";
+        let payload = b"agent-42".to_vec();
+        let watermarked = embed_text_watermark(text, &payload);
+
+        let extracted = extract_text_watermark(&watermarked).unwrap();
+        assert_eq!(extracted, payload);
+    }
+
+    #[test]
+    fn test_watermark_preserves_visible_text() {
+        let text = "Hello world";
+        let watermarked = embed_text_watermark(text, b"x");
+        let stripped: String = watermarked
+            .chars()
+            .filter(|c| !matches!(*c, ZW_SENTINEL | ZW_ZERO | ZW_ONE))
+            .collect();
+        assert_eq!(stripped, text);
+    }
+
+    #[test]
+    fn test_watermark_survives_surrounding_whitespace() {
+        let watermarked = embed_text_watermark("Hello world", b"ok");
+        let padded = format!("  \n{}\t\n  ", watermarked);
+        assert_eq!(extract_text_watermark(&padded).unwrap(), b"ok".to_vec());
+    }
+
+    #[test]
+    fn test_extract_absent_watermark_returns_none() {
+        assert!(extract_text_watermark("just plain text").is_none());
+    }
+
+    // --- C2PA VERIFICATION TESTS ---
+
+    #[test]
+    fn test_verify_c2pa_manifest_roundtrip() {
+        let signed = sign_c2pa_manifest("hello world", "agent-1").unwrap();
+        assert!(verify_c2pa_manifest(&signed).unwrap());
+    }
+
+    #[test]
+    fn test_verify_c2pa_manifest_rejects_tampered_hash() {
+        let signed = sign_c2pa_manifest("hello world", "agent-1").unwrap();
+        let mut parsed: serde_json::Value = serde_json::from_str(&signed).unwrap();
+        parsed["content_hash"] = serde_json::Value::String("0".repeat(64));
+        assert!(!verify_c2pa_manifest(&parsed.to_string()).unwrap());
+    }
+
+    #[test]
+    fn test_verify_c2pa_manifest_rejects_malformed_json() {
+        assert!(verify_c2pa_manifest("not json").is_err());
+    }
+
+    #[test]
+    fn test_extract_png_text_chunk_roundtrip() {
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        let mut chunk_data = Vec::new();
+        chunk_data.extend_from_slice(b"AgentShield-C2PA");
+        chunk_data.push(0);
+        chunk_data.extend_from_slice(b"{\"ok\":true}");
+
+        png.extend_from_slice(&(chunk_data.len() as u32).to_be_bytes());
+        png.extend_from_slice(b"tEXt");
+        png.extend_from_slice(&chunk_data);
+        png.extend_from_slice(&[0u8; 4]); // CRC (unchecked by the extractor)
+
+        let found = extract_png_text_chunks(&png, "AgentShield-C2PA");
+        assert_eq!(found, vec![b"{\"ok\":true}".as_slice()]);
+    }
+
+    #[test]
+    fn test_extract_png_text_chunk_handles_truncated_input() {
+        let mut png = b"\x89PNG\r\n\x1a\n".to_vec();
+        png.extend_from_slice(&1000u32.to_be_bytes()); // claims far more data than present
+        png.extend_from_slice(b"tEXt");
+
+        let found = extract_png_text_chunks(&png, "AgentShield-C2PA");
+        assert!(found.is_empty());
+    }
+
+    #[test]
+    fn test_extract_jpeg_com_segment_roundtrip() {
+        let mut jpeg = b"\xff\xd8".to_vec();
+        let data = b"AgentShield-C2PA-payload";
+        jpeg.push(0xFF);
+        jpeg.push(0xFE);
+        jpeg.extend_from_slice(&((data.len() + 2) as u16).to_be_bytes());
+        jpeg.extend_from_slice(data);
+        jpeg.push(0xFF);
+        jpeg.push(0xD9); // EOI
+
+        let found = extract_jpeg_com_segments(&jpeg);
+        assert_eq!(found, vec![data.as_slice()]);
+    }
+
+    #[test]
+    fn test_extract_jpeg_com_segment_skips_standalone_markers() {
+        // TEM (0x01) and the RST0-RST7 restart markers have no length field. A parser that
+        // assumes every marker is length-prefixed would misread two of the COM segment's own
+        // bytes as a bogus length and desync, missing the real segment entirely.
+        let mut jpeg = b"\xff\xd8".to_vec();
+        jpeg.push(0xFF);
+        jpeg.push(0x01); // TEM, standalone
+        jpeg.push(0xFF);
+        jpeg.push(0xD3); // RST3, standalone
+
+        let data = b"AgentShield-C2PA-payload";
+        jpeg.push(0xFF);
+        jpeg.push(0xFE);
+        jpeg.extend_from_slice(&((data.len() + 2) as u16).to_be_bytes());
+        jpeg.extend_from_slice(data);
+        jpeg.push(0xFF);
+        jpeg.push(0xD9); // EOI
+
+        let found = extract_jpeg_com_segments(&jpeg);
+        assert_eq!(found, vec![data.as_slice()]);
+    }
 }
 